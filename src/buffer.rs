@@ -0,0 +1,243 @@
+// pool/src/buffer.rs
+// SPDX-License-Identifier: GPL-2.0-or-later
+// Copyright (c) 2025 Canmi
+
+use rfs_utils::{log, LogLevel};
+use thiserror::Error;
+
+// Largest block size a size-class may request; anything above this is
+// dropped during sanitization rather than silently allocated.
+pub const MAX_SIZE: usize = 64 * 1024 * 1024;
+
+// Handle into a `StaticBufferPool`, packing `(class_idx << 16) | block_idx`
+// into a `u32`. Kept distinct from `placement::StoreAddr` even though the
+// bit layout matches: the two address spaces (buffer size-class/block vs.
+// pool_id/slot) are unrelated, and sharing one type would let a handle from
+// one pool be passed, and silently misinterpreted, by the other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferAddr(u32);
+
+impl BufferAddr {
+    fn new(class_idx: u16, block_idx: u16) -> Self {
+        BufferAddr(((class_idx as u32) << 16) | block_idx as u32)
+    }
+
+    pub fn raw(&self) -> u32 {
+        self.0
+    }
+
+    pub fn from_raw(raw: u32) -> Self {
+        BufferAddr(raw)
+    }
+
+    fn class_idx(&self) -> u16 {
+        (self.0 >> 16) as u16
+    }
+
+    fn block_idx(&self) -> u16 {
+        (self.0 & 0xFFFF) as u16
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum BufferError {
+    #[error("Staging buffer pool is full for this size class.")]
+    StoreFull,
+    #[error("Data of {0} bytes is too large for any configured size class.")]
+    DataTooLarge(usize),
+    #[error("Buffer handle does not refer to a valid, occupied slot.")]
+    InvalidAddr,
+}
+
+struct SizeClass {
+    block_size: usize,
+    storage: Vec<u8>,
+    // Stack of free block indices within this class.
+    free: Vec<u16>,
+    // Length actually written into each block, indexed by block index.
+    lens: Vec<usize>,
+}
+
+impl SizeClass {
+    fn new(num_blocks: u16, block_size: usize) -> Self {
+        SizeClass {
+            block_size,
+            storage: vec![0u8; num_blocks as usize * block_size],
+            free: (0..num_blocks).rev().collect(),
+            lens: vec![0; num_blocks as usize],
+        }
+    }
+
+    fn slot(&self, block_idx: u16) -> std::ops::Range<usize> {
+        let start = block_idx as usize * self.block_size;
+        start..start + self.block_size
+    }
+}
+
+// Bucketed staging buffer pool, modeled on sat-rs's `StaticMemoryPool`: a
+// fixed set of pre-allocated size classes that writes borrow from instead of
+// allocating on the heap per call.
+pub struct StaticBufferPool {
+    classes: Vec<SizeClass>,
+}
+
+impl StaticBufferPool {
+    // `config` is `(num_blocks, block_size)` per size class. Classes with
+    // `num_blocks == 0` or `block_size` above `MAX_SIZE` are discarded, and
+    // the survivors are sorted ascending by `block_size`.
+    pub fn new(config: Vec<(u16, usize)>) -> Self {
+        let mut config = config;
+        config.retain(|&(num_blocks, block_size)| {
+            if num_blocks == 0 {
+                log(LogLevel::Warn, "Dropping buffer size class with num_blocks == 0.");
+                return false;
+            }
+            if block_size > MAX_SIZE {
+                log(
+                    LogLevel::Warn,
+                    &format!("Dropping buffer size class of {} bytes (exceeds MAX_SIZE).", block_size),
+                );
+                return false;
+            }
+            true
+        });
+        config.sort_by_key(|&(_, block_size)| block_size);
+
+        let classes = config
+            .into_iter()
+            .map(|(num_blocks, block_size)| SizeClass::new(num_blocks, block_size))
+            .collect();
+
+        StaticBufferPool { classes }
+    }
+
+    fn class_for(&self, len: usize) -> Option<usize> {
+        self.classes.iter().position(|c| c.block_size >= len)
+    }
+
+    // Stages `data` in the smallest size class that fits it, returning a
+    // handle encoding `(class_idx << 16) | block_idx`.
+    pub fn add(&mut self, data: &[u8]) -> Result<BufferAddr, BufferError> {
+        let class_idx = self.class_for(data.len()).ok_or(BufferError::DataTooLarge(data.len()))?;
+        let class = &mut self.classes[class_idx];
+        let block_idx = class.free.pop().ok_or(BufferError::StoreFull)?;
+
+        let range = class.slot(block_idx);
+        class.storage[range.start..range.start + data.len()].copy_from_slice(data);
+        class.lens[block_idx as usize] = data.len();
+
+        Ok(BufferAddr::new(class_idx as u16, block_idx))
+    }
+
+    // Copies the staged bytes for `addr` into `buf`, returning how many bytes
+    // were written. If `buf` is shorter than the stored data, only `buf.len()`
+    // bytes are copied (a short read) rather than panicking.
+    pub fn read(&self, addr: BufferAddr, buf: &mut [u8]) -> Result<usize, BufferError> {
+        let (class, block_idx) = self.lookup(addr)?;
+        let len = class.lens[block_idx as usize].min(buf.len());
+        let range = class.slot(block_idx);
+        buf[..len].copy_from_slice(&class.storage[range.start..range.start + len]);
+        Ok(len)
+    }
+
+    // Applies `f` to the live bytes for `addr` in place.
+    pub fn modify<F: FnOnce(&mut [u8])>(&mut self, addr: BufferAddr, f: F) -> Result<(), BufferError> {
+        let class_idx = addr.class_idx() as usize;
+        let block_idx = addr.block_idx();
+        let class = self.classes.get_mut(class_idx).ok_or(BufferError::InvalidAddr)?;
+        let len = *class.lens.get(block_idx as usize).ok_or(BufferError::InvalidAddr)?;
+        if len == 0 {
+            return Err(BufferError::InvalidAddr);
+        }
+        let range = class.slot(block_idx);
+        f(&mut class.storage[range.start..range.start + len]);
+        Ok(())
+    }
+
+    // Returns the block backing `addr` to its size class's free list.
+    pub fn free(&mut self, addr: BufferAddr) -> Result<(), BufferError> {
+        let class_idx = addr.class_idx() as usize;
+        let block_idx = addr.block_idx();
+        let class = self.classes.get_mut(class_idx).ok_or(BufferError::InvalidAddr)?;
+        let len = class.lens.get_mut(block_idx as usize).ok_or(BufferError::InvalidAddr)?;
+        if *len == 0 {
+            return Err(BufferError::InvalidAddr);
+        }
+        *len = 0;
+        class.free.push(block_idx);
+        Ok(())
+    }
+
+    fn lookup(&self, addr: BufferAddr) -> Result<(&SizeClass, u16), BufferError> {
+        let class_idx = addr.class_idx() as usize;
+        let block_idx = addr.block_idx();
+        let class = self.classes.get(class_idx).ok_or(BufferError::InvalidAddr)?;
+        if class.lens.get(block_idx as usize).copied().unwrap_or(0) == 0 {
+            return Err(BufferError::InvalidAddr);
+        }
+        Ok((class, block_idx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exhausts_with_store_full() {
+        let mut pool = StaticBufferPool::new(vec![(1, 16)]);
+        pool.add(b"first").unwrap();
+        let err = pool.add(b"second").unwrap_err();
+        assert!(matches!(err, BufferError::StoreFull));
+    }
+
+    #[test]
+    fn rejects_oversize_data() {
+        let mut pool = StaticBufferPool::new(vec![(4, 16)]);
+        let err = pool.add(&[0u8; 17]).unwrap_err();
+        assert!(matches!(err, BufferError::DataTooLarge(17)));
+    }
+
+    #[test]
+    fn buffer_addr_round_trips_through_raw() {
+        let addr = BufferAddr::new(3, 42);
+        let restored = BufferAddr::from_raw(addr.raw());
+        assert_eq!(restored.class_idx(), 3);
+        assert_eq!(restored.block_idx(), 42);
+    }
+
+    #[test]
+    fn round_trips_read_and_modify() {
+        let mut pool = StaticBufferPool::new(vec![(2, 8), (2, 64)]);
+        let addr = pool.add(b"hello").unwrap();
+
+        let mut buf = [0u8; 8];
+        let n = pool.read(addr, &mut buf).unwrap();
+        assert_eq!(&buf[..n], b"hello");
+
+        pool.modify(addr, |bytes| bytes[0] = b'H').unwrap();
+        let n = pool.read(addr, &mut buf).unwrap();
+        assert_eq!(&buf[..n], b"Hello");
+
+        pool.free(addr).unwrap();
+        assert!(pool.read(addr, &mut buf).is_err());
+    }
+
+    #[test]
+    fn read_into_short_buffer_truncates_instead_of_panicking() {
+        let mut pool = StaticBufferPool::new(vec![(1, 64)]);
+        let addr = pool.add(&[7u8; 50]).unwrap();
+
+        let mut buf = [0u8; 10];
+        let n = pool.read(addr, &mut buf).unwrap();
+        assert_eq!(n, 10);
+        assert_eq!(buf, [7u8; 10]);
+    }
+
+    #[test]
+    fn sanitizes_invalid_classes_and_sorts_by_block_size() {
+        let pool = StaticBufferPool::new(vec![(0, 32), (4, 128), (2, 16)]);
+        let sizes: Vec<usize> = pool.classes.iter().map(|c| c.block_size).collect();
+        assert_eq!(sizes, vec![16, 128]);
+    }
+}
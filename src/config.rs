@@ -6,6 +6,11 @@ pub fn generate_default_pools_config() -> &'static str {
     r#"# Configuration for rfs storage pools.
 # Each pool represents a storage location that rfs will manage.
 
+# Picks which pool a newly written file lands in.
+# One of: "round_robin" (default), "weighted_free_space", "fill_largest_remaining".
+[strategy]
+kind = "round_robin"
+
 [[pool]]
 pool_id = 1
 is_removable = false
@@ -0,0 +1,55 @@
+// pool/src/health.rs
+// SPDX-License-Identifier: GPL-2.0-or-later
+// Copyright (c) 2025 Canmi
+
+use crate::mount::Pool;
+use crate::placement;
+use std::path::Path;
+
+// Per-pool snapshot returned by `scan_pools`.
+#[derive(Debug, Clone)]
+pub struct PoolStatus {
+    pub pool_id: u64,
+    pub path: String,
+    pub online: bool,
+    pub total: u64,
+    pub free: u64,
+}
+
+// Runs the per-pool accessibility and free-space check concurrently instead
+// of serially, so one slow/offline pool doesn't hold up the others. A
+// removable pool that isn't reachable reports `online: false` rather than
+// failing the whole scan.
+pub async fn scan_pools(pools: &[Pool]) -> Vec<PoolStatus> {
+    let tasks: Vec<_> = pools
+        .iter()
+        .cloned()
+        .map(|pool| tokio::task::spawn_blocking(move || scan_one(pool)))
+        .collect();
+
+    let mut statuses = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        match task.await {
+            Ok(status) => statuses.push(status),
+            Err(_) => continue,
+        }
+    }
+    statuses
+}
+
+fn scan_one(pool: Pool) -> PoolStatus {
+    let online = Path::new(&pool.path).is_dir();
+    let (total, free) = if online {
+        placement::disk_space(&pool.path)
+    } else {
+        (0, 0)
+    };
+
+    PoolStatus {
+        pool_id: pool.pool_id,
+        path: pool.path,
+        online,
+        total,
+        free,
+    }
+}
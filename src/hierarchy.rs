@@ -0,0 +1,135 @@
+// pool/src/hierarchy.rs
+// SPDX-License-Identifier: GPL-2.0-or-later
+// Copyright (c) 2025 Canmi
+
+use crate::mount::{Mount, Pool, PoolError};
+use crate::placement::StrategyConfig;
+use rfs_utils::{log, LogLevel};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct PoolsFile {
+    #[serde(default)]
+    pub(crate) pool: Vec<Pool>,
+    #[serde(default)]
+    pub(crate) mount: Vec<Mount>,
+    // `None` when this file has no `[strategy]` table at all, as opposed to
+    // one that's merely empty -- distinguishing the two is what lets a
+    // host-specific override inherit a site-wide ancestor's strategy instead
+    // of silently resetting it to the default.
+    #[serde(default)]
+    pub(crate) strategy: Option<StrategyConfig>,
+    #[serde(default)]
+    pub(crate) include: Vec<String>,
+}
+
+pub(crate) struct Assembled {
+    pub(crate) pools: Vec<Pool>,
+    pub(crate) mounts: Vec<Mount>,
+    pub(crate) strategy: StrategyConfig,
+}
+
+// Assembles the full pool configuration for `main_path`, the way rustfmt
+// merges configs from parent directories: every ancestor directory's file
+// with the same name is read too, farthest-first, so the explicit file the
+// caller passed in always has the final say. An optional `include = [...]`
+// key pulls in sibling fragment files at the same precedence as the file
+// that references them.
+pub(crate) fn assemble(main_path: &Path) -> Result<Assembled, PoolError> {
+    let mut levels: Vec<(PathBuf, PoolsFile)> = Vec::new();
+    for ancestor in ancestor_files(main_path) {
+        levels.push((ancestor.clone(), read_pools_file(&ancestor)?));
+    }
+    levels.push((main_path.to_path_buf(), read_pools_file(main_path)?));
+
+    let mut merged: HashMap<u64, Pool> = HashMap::new();
+    let mut mounts = Vec::new();
+    let mut strategy: Option<StrategyConfig> = None;
+
+    for (source_path, file) in &levels {
+        let mut level_pools: HashMap<u64, Pool> = HashMap::new();
+        let mut add_sibling = |pool: Pool, origin: &Path| -> Result<(), PoolError> {
+            match level_pools.get(&pool.pool_id) {
+                Some(existing) if existing.path != pool.path => Err(PoolError::ConflictingPool {
+                    pool_id: pool.pool_id,
+                    a: existing.path.clone(),
+                    b: pool.path.clone(),
+                    file: origin.display().to_string(),
+                }),
+                _ => {
+                    level_pools.insert(pool.pool_id, pool);
+                    Ok(())
+                }
+            }
+        };
+
+        for pool in file.pool.iter().cloned() {
+            add_sibling(pool, source_path)?;
+        }
+        for include_name in &file.include {
+            let include_path = resolve_include(source_path, include_name);
+            let fragment = read_pools_file(&include_path)?;
+            for pool in fragment.pool {
+                add_sibling(pool, &include_path)?;
+            }
+            mounts.extend(fragment.mount);
+        }
+
+        mounts.extend(file.mount.iter().cloned());
+        if let Some(explicit) = &file.strategy {
+            strategy = Some(explicit.clone());
+        }
+
+        if source_path != main_path {
+            log(
+                LogLevel::Debug,
+                &format!("Merged ancestor pool config from {}", source_path.display()),
+            );
+        }
+
+        for (pool_id, pool) in level_pools {
+            merged.insert(pool_id, pool);
+        }
+    }
+
+    let mut pools: Vec<Pool> = merged.into_values().collect();
+    pools.sort_by_key(|p| p.pool_id);
+
+    Ok(Assembled { pools, mounts, strategy: strategy.unwrap_or_default() })
+}
+
+fn read_pools_file(path: &Path) -> Result<PoolsFile, PoolError> {
+    let content = fs::read_to_string(path)?;
+    Ok(toml::from_str(&content)?)
+}
+
+fn resolve_include(source_path: &Path, include_name: &str) -> PathBuf {
+    source_path
+        .parent()
+        .map(|dir| dir.join(include_name))
+        .unwrap_or_else(|| PathBuf::from(include_name))
+}
+
+// Walks upward from `main_path`'s directory looking for files with the same
+// name, e.g. site-wide `pool.toml` defaults above a host-specific one.
+// Returns them ordered farthest-first so the caller can merge nearest-last.
+fn ancestor_files(main_path: &Path) -> Vec<PathBuf> {
+    let Some(file_name) = main_path.file_name() else {
+        return Vec::new();
+    };
+
+    let mut found = Vec::new();
+    let mut dir = main_path.parent().and_then(Path::parent);
+    while let Some(d) = dir {
+        let candidate = d.join(file_name);
+        if candidate.is_file() {
+            found.push(candidate);
+        }
+        dir = d.parent();
+    }
+    found.reverse();
+    found
+}
@@ -2,7 +2,16 @@
 // SPDX-License-Identifier: GPL-2.0-or-later
 // Copyright (c) 2025 Canmi
 
+mod buffer;
 mod config;
+mod health;
+mod hierarchy;
+mod mmap;
 mod mount;
+mod placement;
 
-pub use mount::{load_and_mount_pools, Pool, POOLS, PoolError};
\ No newline at end of file
+pub use buffer::{BufferAddr, BufferError, StaticBufferPool};
+pub use health::{scan_pools, PoolStatus};
+pub use mmap::FilePool;
+pub use mount::{load_and_mount_pools, Pool, POOLS, PoolError};
+pub use placement::{select_pool, PoolSelector, StoreAddr, StrategyConfig, StrategyKind};
\ No newline at end of file
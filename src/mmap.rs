@@ -0,0 +1,121 @@
+// pool/src/mmap.rs
+// SPDX-License-Identifier: GPL-2.0-or-later
+// Copyright (c) 2025 Canmi
+
+use crate::mount::{PoolError, POOLS};
+use std::fs::File;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+#[cfg(not(windows))]
+use memmap2::Mmap;
+
+enum Backing {
+    #[cfg(not(windows))]
+    Mapped(Mmap),
+    // Only constructed on platforms without usable mmap semantics.
+    #[allow(dead_code)]
+    Owned(Vec<u8>),
+}
+
+impl Backing {
+    #[cfg(not(windows))]
+    fn load(file: &File) -> std::io::Result<Self> {
+        // Safety: the mapped file is only ever read through `FilePool::get`,
+        // and entries are never removed or truncated for the lifetime of the
+        // pool, so the map outlives every slice handed out from it.
+        unsafe { Mmap::map(file).map(Backing::Mapped) }
+    }
+
+    #[cfg(windows)]
+    fn load(mut file: &File) -> std::io::Result<Self> {
+        use std::io::Read;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+        Ok(Backing::Owned(buf))
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            #[cfg(not(windows))]
+            Backing::Mapped(map) => &map[..],
+            Backing::Owned(buf) => &buf[..],
+        }
+    }
+}
+
+// Zero-copy read accessor for files stored in pool directories. Each file is
+// mapped (or, on platforms without usable mmap semantics, read fully into an
+// owned buffer) exactly once, and the resulting backing storage is
+// heap-allocated and never moved or dropped while the pool lives: growing
+// the outer `Vec` only relocates the `Box` pointers it holds, never the data
+// a `Box` points to, so slices handed out by `get` stay valid even after
+// later calls map more files.
+pub struct FilePool {
+    entries: Mutex<Vec<(u64, PathBuf, Box<Backing>)>>,
+}
+
+impl FilePool {
+    pub fn new() -> Self {
+        FilePool { entries: Mutex::new(Vec::new()) }
+    }
+
+    // Returns a zero-copy slice over `rel_path` within `pool_id`, mapping
+    // (or reading) the file on first access and reusing the mapping on
+    // every call after that.
+    pub fn get(&self, pool_id: u64, rel_path: &str) -> Result<&[u8], PoolError> {
+        ensure_contained(rel_path)?;
+
+        let mut entries = self.entries.lock().unwrap();
+
+        if let Some((_, _, backing)) = entries
+            .iter()
+            .find(|(id, path, _)| *id == pool_id && path.as_os_str() == rel_path)
+        {
+            return Ok(extend_lifetime(backing.as_slice()));
+        }
+
+        let full_path = resolve_path(pool_id, rel_path)?;
+        let file = File::open(&full_path)?;
+        let backing = Box::new(Backing::load(&file)?);
+        let slice = extend_lifetime(backing.as_slice());
+
+        entries.push((pool_id, PathBuf::from(rel_path), backing));
+        Ok(slice)
+    }
+}
+
+impl Default for FilePool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn resolve_path(pool_id: u64, rel_path: &str) -> Result<PathBuf, PoolError> {
+    let pools = POOLS.lock().unwrap();
+    let pool = pools
+        .iter()
+        .find(|p| p.pool_id == pool_id)
+        .ok_or(PoolError::PoolNotFound(pool_id))?;
+    Ok(PathBuf::from(&pool.path).join(rel_path))
+}
+
+// Rejects absolute paths and `..` components so `rel_path` can never resolve
+// outside the pool directory it's joined onto.
+fn ensure_contained(rel_path: &str) -> Result<(), PoolError> {
+    let path = Path::new(rel_path);
+    let contained = path.is_relative() && !path.components().any(|c| matches!(c, Component::ParentDir));
+    if contained {
+        Ok(())
+    } else {
+        Err(PoolError::InvalidRelPath(rel_path.to_string()))
+    }
+}
+
+// The slice points into heap storage owned by a `Box` that lives inside
+// `FilePool::entries` and is only ever appended to, never removed, so it is
+// valid for as long as the `FilePool` itself -- safe to detach from the
+// `MutexGuard`'s shorter borrow.
+fn extend_lifetime<'a>(slice: &[u8]) -> &'a [u8] {
+    unsafe { std::slice::from_raw_parts(slice.as_ptr(), slice.len()) }
+}
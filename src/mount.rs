@@ -3,9 +3,12 @@
 // Copyright (c) 2025 Canmi
 
 use crate::config;
+use crate::hierarchy;
+use crate::placement;
 use once_cell::sync::Lazy;
 use rfs_utils::{log, LogLevel};
 use serde::Deserialize;
+use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 use std::sync::Mutex;
@@ -18,14 +21,6 @@ pub struct Mount {
     pub mount_point: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct PoolsFile {
-    pool: Vec<Pool>,
-    // This field is now optional.
-    #[serde(default)]
-    mount: Vec<Mount>,
-}
-
 #[derive(Debug, Clone, Deserialize)]
 pub struct Pool {
     pub pool_id: u64,
@@ -41,6 +36,14 @@ pub enum PoolError {
     InvalidIdSequence,
     #[error("Default pool config created at '{0}'. Please review and configure it before restarting.")]
     MustConfigure(String),
+    #[error("No eligible pool available to place a new file.")]
+    NoEligiblePool,
+    #[error("Pool {0} is not currently mounted.")]
+    PoolNotFound(u64),
+    #[error("Pool {pool_id} has conflicting paths '{a}' and '{b}' across sibling sources (last seen in {file}).")]
+    ConflictingPool { pool_id: u64, a: String, b: String, file: String },
+    #[error("'{0}' is not a valid relative path within a pool.")]
+    InvalidRelPath(String),
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
     #[error("TOML parsing error: {0}")]
@@ -49,8 +52,61 @@ pub enum PoolError {
 
 pub static POOLS: Lazy<Mutex<Vec<Pool>>> = Lazy::new(|| Mutex::new(Vec::new()));
 
+// Drops empty-path entries, de-duplicates by `pool_id` (keeping the first
+// occurrence), and renumbers the survivors to a dense `1..=N` sequence. This
+// lets an operator delete a pool from the middle of the list without having
+// to hand-renumber everything else.
+fn sanitize(pools: Vec<Pool>) -> Vec<Pool> {
+    let mut seen = HashSet::new();
+    let mut pools: Vec<Pool> = pools
+        .into_iter()
+        .filter(|p| {
+            if p.path.is_empty() {
+                log(
+                    LogLevel::Warn,
+                    &format!("Dropping pool {} with an empty path.", p.pool_id),
+                );
+                return false;
+            }
+            if !seen.insert(p.pool_id) {
+                log(
+                    LogLevel::Warn,
+                    &format!("Dropping duplicate pool_id {} (keeping first occurrence).", p.pool_id),
+                );
+                return false;
+            }
+            true
+        })
+        .collect();
+
+    pools.sort_by_key(|p| p.pool_id);
+
+    for (index, pool) in pools.iter_mut().enumerate() {
+        let new_id = (index as u64) + 1;
+        if pool.pool_id != new_id {
+            log(
+                LogLevel::Warn,
+                &format!("Renumbering pool_id {} -> {}.", pool.pool_id, new_id),
+            );
+            pool.pool_id = new_id;
+        }
+    }
+
+    pools
+}
+
 // This function now returns the loaded pools and mounts.
-pub async fn load_and_mount_pools(path_str: &str) -> Result<(Vec<Pool>, Vec<Mount>), PoolError> {
+//
+// `strict` preserves the historical hard-fail behavior on gapped `pool_id`s.
+// Duplicate `pool_id`s are already resolved by `hierarchy::assemble` before
+// this check: nearer files override farther ones, and differing paths within
+// one level fail with `ConflictingPool`. Non-strict callers instead get
+// `sanitize`d, resilient loading. Both modes return the number of pools that
+// survived, so callers can assert a minimum.
+pub async fn load_and_mount_pools(
+    path_str: &str,
+    strict: bool,
+) -> Result<(Vec<Pool>, Vec<Mount>, usize), PoolError> {
     let path = Path::new(path_str);
 
     if !path.exists() {
@@ -66,22 +122,33 @@ pub async fn load_and_mount_pools(path_str: &str) -> Result<(Vec<Pool>, Vec<Moun
     }
 
     log(LogLevel::Info, &format!("Loading pools from {}", path_str));
-    let content = fs::read_to_string(path)?;
-    let pools_from_file: PoolsFile = toml::from_str(&content)?;
-    let mut pools = pools_from_file.pool;
-    let mounts = pools_from_file.mount;
+    let assembled = hierarchy::assemble(path)?;
+    let mut pools = assembled.pools;
+    let mounts = assembled.mounts;
+    let strategy = assembled.strategy;
 
     if pools.is_empty() {
         return Err(PoolError::EmptyPools);
     }
 
-    pools.sort_by_key(|p| p.pool_id);
-    for (index, pool) in pools.iter().enumerate() {
-        if pool.pool_id != (index as u64) + 1 {
-            return Err(PoolError::InvalidIdSequence);
+    if strict {
+        pools.sort_by_key(|p| p.pool_id);
+        for (index, pool) in pools.iter().enumerate() {
+            if pool.pool_id != (index as u64) + 1 {
+                return Err(PoolError::InvalidIdSequence);
+            }
+        }
+        log(LogLevel::Debug, "Pool IDs are sequential and unique.");
+    } else {
+        pools = sanitize(pools);
+        if pools.is_empty() {
+            return Err(PoolError::EmptyPools);
         }
+        log(
+            LogLevel::Debug,
+            &format!("Sanitized pool config: {} pool(s) survived.", pools.len()),
+        );
     }
-    log(LogLevel::Debug, "Pool IDs are sequential and unique.");
 
     for pool in &pools {
         if !pool.is_removable {
@@ -108,9 +175,44 @@ pub async fn load_and_mount_pools(path_str: &str) -> Result<(Vec<Pool>, Vec<Moun
     }
     log(LogLevel::Debug, "Pool path accessibility check complete.");
 
+    let surviving = pools.len();
+
     let mut pools_guard = POOLS.lock().unwrap();
     *pools_guard = pools.clone();
+    drop(pools_guard);
+
+    placement::set_strategy(strategy.kind);
 
     log(LogLevel::Info, "Storage pools mounted successfully.");
-    Ok((pools, mounts))
+    Ok((pools, mounts, surviving))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(pool_id: u64, path: &str) -> Pool {
+        Pool { pool_id, is_removable: false, path: path.to_string() }
+    }
+
+    #[test]
+    fn sanitize_drops_empty_paths_and_duplicates() {
+        let sanitized = sanitize(vec![
+            pool(1, "/mnt/a"),
+            pool(2, ""),
+            pool(1, "/mnt/a-duplicate"),
+            pool(3, "/mnt/c"),
+        ]);
+
+        let paths: Vec<&str> = sanitized.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(paths, vec!["/mnt/a", "/mnt/c"]);
+    }
+
+    #[test]
+    fn sanitize_renumbers_to_a_dense_sequence() {
+        let sanitized = sanitize(vec![pool(5, "/mnt/a"), pool(9, "/mnt/b")]);
+
+        let ids: Vec<u64> = sanitized.iter().map(|p| p.pool_id).collect();
+        assert_eq!(ids, vec![1, 2]);
+    }
 }
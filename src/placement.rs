@@ -0,0 +1,279 @@
+// pool/src/placement.rs
+// SPDX-License-Identifier: GPL-2.0-or-later
+// Copyright (c) 2025 Canmi
+
+use crate::mount::{Pool, PoolError, POOLS};
+use once_cell::sync::Lazy;
+use rfs_utils::{log, LogLevel};
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+// Packs a chosen pool_id into the high 16 bits and an intra-pool slot/inode
+// hint into the low 16 bits, mirroring sat-rs's `StoreAddr`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoreAddr(u32);
+
+impl StoreAddr {
+    pub fn new(pool_id: u16, slot: u16) -> Self {
+        StoreAddr(((pool_id as u32) << 16) | slot as u32)
+    }
+
+    pub fn raw(&self) -> u32 {
+        self.0
+    }
+
+    pub fn from_raw(raw: u32) -> Self {
+        StoreAddr(raw)
+    }
+
+    pub fn pool_id(&self) -> u16 {
+        (self.0 >> 16) as u16
+    }
+
+    pub fn slot(&self) -> u16 {
+        (self.0 & 0xFFFF) as u16
+    }
+}
+
+// Placement strategy selectable from `pool.toml`'s `[strategy]` table.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyKind {
+    #[default]
+    RoundRobin,
+    WeightedFreeSpace,
+    FillLargestRemaining,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StrategyConfig {
+    #[serde(default)]
+    pub kind: StrategyKind,
+}
+
+// Chooses a target pool for a newly written file of a given size.
+pub trait PoolSelector: Send + Sync {
+    fn select(&self, size: u64, pools: &[Pool]) -> Result<(Pool, StoreAddr), PoolError>;
+}
+
+fn eligible(pools: &[Pool]) -> Vec<&Pool> {
+    pools
+        .iter()
+        .filter(|p| !p.is_removable || is_reachable(&p.path))
+        .collect()
+}
+
+fn is_reachable(path: &str) -> bool {
+    Path::new(path).is_dir()
+}
+
+// Best-effort total/free space probe, shared with `health::scan_pools`.
+// Returns `(0, 0)` rather than erroring the whole selection on a transient
+// I/O failure; `scan_pools` then reports that pool with zero totals.
+pub(crate) fn disk_space(path: &str) -> (u64, u64) {
+    #[cfg(unix)]
+    {
+        use std::ffi::CString;
+        use std::mem::MaybeUninit;
+
+        let Ok(c_path) = CString::new(path) else {
+            return (0, 0);
+        };
+        #[allow(clippy::unnecessary_cast)]
+        unsafe {
+            let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+            if libc::statvfs(c_path.as_ptr(), stat.as_mut_ptr()) != 0 {
+                return (0, 0);
+            }
+            let stat = stat.assume_init();
+            let frsize = stat.f_frsize as u64;
+            ((stat.f_blocks as u64).saturating_mul(frsize), (stat.f_bavail as u64).saturating_mul(frsize))
+        }
+    }
+    #[cfg(windows)]
+    {
+        use std::ffi::OsStr;
+        use std::iter::once;
+        use std::os::windows::ffi::OsStrExt;
+        use winapi::um::fileapi::GetDiskFreeSpaceExW;
+
+        let wide: Vec<u16> = OsStr::new(path).encode_wide().chain(once(0)).collect();
+        let mut free_bytes: u64 = 0;
+        let mut total_bytes: u64 = 0;
+        unsafe {
+            if GetDiskFreeSpaceExW(
+                wide.as_ptr(),
+                &mut free_bytes as *mut u64 as *mut _,
+                &mut total_bytes as *mut u64 as *mut _,
+                std::ptr::null_mut(),
+            ) == 0
+            {
+                return (0, 0);
+            }
+        }
+        (total_bytes, free_bytes)
+    }
+}
+
+pub(crate) fn disk_free_bytes(path: &str) -> u64 {
+    disk_space(path).1
+}
+
+// Picks pools in round-robin order, skipping unreachable removable pools.
+pub struct RoundRobinSelector {
+    cursor: AtomicU64,
+}
+
+impl RoundRobinSelector {
+    pub fn new() -> Self {
+        RoundRobinSelector { cursor: AtomicU64::new(0) }
+    }
+}
+
+impl Default for RoundRobinSelector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PoolSelector for RoundRobinSelector {
+    fn select(&self, _size: u64, pools: &[Pool]) -> Result<(Pool, StoreAddr), PoolError> {
+        let candidates = eligible(pools);
+        if candidates.is_empty() {
+            return Err(PoolError::NoEligiblePool);
+        }
+        let idx = (self.cursor.fetch_add(1, Ordering::Relaxed) as usize) % candidates.len();
+        let pool = candidates[idx];
+        let slot = self.cursor.load(Ordering::Relaxed) as u16;
+        Ok((pool.clone(), StoreAddr::new(pool.pool_id as u16, slot)))
+    }
+}
+
+// Smooth weighted round-robin over free space: each call, every pool's credit
+// grows by its free-space weight, the pool with the highest credit is picked,
+// and its credit is then discounted by the total weight, so traffic spreads
+// proportionally to the
+// free space each pool reports instead of always hammering the emptiest one.
+pub struct WeightedFreeSpaceSelector {
+    credit: Mutex<Vec<i64>>,
+}
+
+impl WeightedFreeSpaceSelector {
+    pub fn new() -> Self {
+        WeightedFreeSpaceSelector { credit: Mutex::new(Vec::new()) }
+    }
+}
+
+impl Default for WeightedFreeSpaceSelector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PoolSelector for WeightedFreeSpaceSelector {
+    fn select(&self, _size: u64, pools: &[Pool]) -> Result<(Pool, StoreAddr), PoolError> {
+        let candidates = eligible(pools);
+        if candidates.is_empty() {
+            return Err(PoolError::NoEligiblePool);
+        }
+        let weights: Vec<i64> = candidates
+            .iter()
+            .map(|p| (disk_free_bytes(&p.path) / 1024).max(1) as i64)
+            .collect();
+
+        let mut credit = self.credit.lock().unwrap();
+        if credit.len() != candidates.len() {
+            *credit = vec![0; candidates.len()];
+        }
+        let total: i64 = weights.iter().sum();
+        for (c, w) in credit.iter_mut().zip(&weights) {
+            *c += w;
+        }
+        let (best_idx, _) = credit
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, c)| **c)
+            .expect("candidates is non-empty");
+        credit[best_idx] -= total;
+
+        let pool = candidates[best_idx];
+        let slot = weights[best_idx] as u16;
+        Ok((pool.clone(), StoreAddr::new(pool.pool_id as u16, slot)))
+    }
+}
+
+// Greedily bin-packs into whichever eligible pool currently reports the most
+// free space, so smaller pools stay untouched until the large one's free
+// space drops below theirs.
+pub struct FillLargestRemainingSelector;
+
+impl FillLargestRemainingSelector {
+    pub fn new() -> Self {
+        FillLargestRemainingSelector
+    }
+}
+
+impl Default for FillLargestRemainingSelector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PoolSelector for FillLargestRemainingSelector {
+    fn select(&self, _size: u64, pools: &[Pool]) -> Result<(Pool, StoreAddr), PoolError> {
+        let candidates = eligible(pools);
+        if candidates.is_empty() {
+            return Err(PoolError::NoEligiblePool);
+        }
+        let (pool, free) = candidates
+            .into_iter()
+            .map(|p| (p, disk_free_bytes(&p.path)))
+            .max_by_key(|(_, free)| *free)
+            .expect("candidates is non-empty");
+        Ok((pool.clone(), StoreAddr::new(pool.pool_id as u16, (free % u16::MAX as u64) as u16)))
+    }
+}
+
+pub(crate) fn selector_for(kind: StrategyKind) -> Box<dyn PoolSelector> {
+    match kind {
+        StrategyKind::RoundRobin => Box::new(RoundRobinSelector::new()),
+        StrategyKind::WeightedFreeSpace => Box::new(WeightedFreeSpaceSelector::new()),
+        StrategyKind::FillLargestRemaining => Box::new(FillLargestRemainingSelector::new()),
+    }
+}
+
+pub(crate) static SELECTOR: Lazy<Mutex<Box<dyn PoolSelector>>> =
+    Lazy::new(|| Mutex::new(selector_for(StrategyKind::default())));
+
+pub(crate) fn set_strategy(kind: StrategyKind) {
+    log(LogLevel::Info, &format!("Using pool placement strategy: {:?}", kind));
+    *SELECTOR.lock().unwrap() = selector_for(kind);
+}
+
+// Single entry point for the rest of rfs: pick a pool for a write of `size`
+// bytes using the currently configured strategy.
+pub fn select_pool(size: u64) -> Result<(Pool, StoreAddr), PoolError> {
+    let pools = POOLS.lock().unwrap().clone();
+    SELECTOR.lock().unwrap().select(size, &pools)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_addr_round_trips_through_raw() {
+        let addr = StoreAddr::new(7, 1234);
+        let restored = StoreAddr::from_raw(addr.raw());
+        assert_eq!(restored.pool_id(), 7);
+        assert_eq!(restored.slot(), 1234);
+    }
+
+    #[test]
+    fn store_addr_packs_pool_id_in_high_bits() {
+        let addr = StoreAddr::new(1, 0);
+        assert_eq!(addr.raw(), 1 << 16);
+    }
+}